@@ -0,0 +1,256 @@
+//! Householder-reflector online QR
+
+use super::*;
+use num_traits::{NumCast, Zero};
+
+/// Online QR decomposition backed by Householder reflections
+///
+/// Each appended vector is first reduced against every previously stored
+/// reflector, then a new reflector annihilating the remaining sub-diagonal
+/// tail is formed and stored together with the resulting column of `R`.
+/// Because reflectors are exactly unitary, the accumulated `Q` stays
+/// orthonormal to machine precision even on nearly dependent input, unlike
+/// MGS.
+pub struct Householder<A: Scalar> {
+    dim: usize,
+    v: Vec<(Array1<A>, A)>,
+}
+
+impl<A: Scalar + Lapack> Householder<A> {
+    pub fn new(dim: usize) -> Self {
+        Householder { dim, v: Vec::new() }
+    }
+
+    /// Apply the `k`-th stored reflector to the tail `a[k..]`, in place
+    fn apply<S>(&self, k: usize, a: &mut ArrayBase<S, Ix1>)
+    where
+        S: DataMut<Elem = A>,
+    {
+        let (v, beta) = &self.v[k];
+        let dot = v
+            .iter()
+            .zip(a.iter().skip(k))
+            .fold(A::zero(), |acc, (&vi, &ai)| acc + vi.conj() * ai);
+        let coef = *beta * dot;
+        for (vi, ai) in v.iter().zip(a.iter_mut().skip(k)) {
+            *ai -= coef * *vi;
+        }
+    }
+
+    /// Run `a` through every stored reflector, returning the reduced coefficients
+    fn reduce<S>(&self, a: &mut ArrayBase<S, Ix1>) -> Array1<A>
+    where
+        S: DataMut<Elem = A>,
+    {
+        let mut coef = Array1::zeros(self.len());
+        for k in 0..self.len() {
+            self.apply(k, a);
+            coef[k] = a[k];
+        }
+        coef
+    }
+}
+
+impl<A: Scalar + Lapack> Orthogonalizer for Householder<A> {
+    type Elem = A;
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn len(&self) -> usize {
+        self.v.len()
+    }
+
+    fn orthogonalize<S>(&self, a: &mut ArrayBase<S, Ix1>) -> Array1<A>
+    where
+        S: DataMut<Elem = A>,
+    {
+        assert_eq!(a.len(), self.dim);
+
+        let coef_head = self.reduce(a);
+        let tail_norm = a
+            .iter()
+            .skip(self.len())
+            .map(|x| x.square())
+            .sum::<A::Real>()
+            .sqrt();
+
+        let mut coef = Array1::zeros(self.len() + 1);
+        coef.slice_mut(s![..self.len()]).assign(&coef_head);
+        coef[self.len()] = A::from_real(tail_norm);
+
+        coef
+    }
+
+    fn append<S>(
+        &mut self,
+        mut a: ArrayBase<S, Ix1>,
+        rtol: A::Real,
+    ) -> Result<Array1<A>, Array1<A>>
+    where
+        S: DataMut<Elem = A>,
+    {
+        assert_eq!(a.len(), self.dim);
+
+        let k = self.len();
+        let coef_head = self.reduce(&mut a);
+
+        let tail = a.slice(s![k..]).to_owned();
+        let tail_norm = tail.iter().map(|x| x.square()).sum::<A::Real>().sqrt();
+
+        let mut coef = Array1::zeros(k + 1);
+        coef.slice_mut(s![..k]).assign(&coef_head);
+
+        if tail_norm < rtol {
+            coef[k] = A::from_real(tail_norm);
+            return Err(coef);
+        }
+
+        let (v, beta, alpha) = make_householder(&tail);
+        self.v.push((v, beta));
+        coef[k] = alpha;
+
+        Ok(coef)
+    }
+
+    fn get_q(&self) -> Q<A> {
+        let mut q = Array2::zeros((self.dim, self.dim));
+        for i in 0..self.dim {
+            q[(i, i)] = A::one();
+        }
+
+        // accumulate the product of reflectors, applied to every column of Q
+        for (v, beta) in self.v.iter().rev() {
+            let k = self.dim - v.len();
+            for col in 0..self.dim {
+                let mut column = q.slice_mut(s![k.., col]);
+                let dot = v
+                    .iter()
+                    .zip(column.iter())
+                    .fold(A::zero(), |acc, (&vi, &qi)| acc + vi.conj() * qi);
+                let coef = *beta * dot;
+                for (vi, qi) in v.iter().zip(column.iter_mut()) {
+                    *qi -= coef * *vi;
+                }
+            }
+        }
+
+        q.slice(s![.., ..self.len()]).to_owned()
+    }
+}
+
+/// Build the Householder reflector `(v, beta)` annihilating all but the first
+/// entry of `x`, together with the resulting (signed) leading entry `alpha`
+fn make_householder<A: Scalar>(x: &Array1<A>) -> (Array1<A>, A, A) {
+    let norm_x = x.iter().map(|v| v.square()).sum::<A::Real>().sqrt();
+
+    let x0 = x[0];
+    let x0_abs = x0.abs();
+    let phase = if x0_abs > A::Real::zero() {
+        x0 / A::from_real(x0_abs)
+    } else {
+        A::one()
+    };
+    let alpha = -phase * A::from_real(norm_x);
+
+    let mut v = x.to_owned();
+    v[0] = v[0] - alpha;
+
+    let v_norm2 = v.iter().map(|z| z.square()).sum::<A::Real>();
+    let two: A::Real = NumCast::from(2.0).unwrap();
+    let beta = if v_norm2 > A::Real::zero() {
+        A::from_real(two / v_norm2)
+    } else {
+        A::zero()
+    };
+
+    (v, beta, alpha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64 as C;
+
+    #[test]
+    fn householder_qr_is_unitary_for_real_input() {
+        let a = array![[1.0, 2.0, 0.0], [0.0, 1.0, -1.0], [3.0, -2.0, 1.0]];
+
+        let columns = a.gencolumns().into_iter().map(|c| c.to_owned());
+        let (q, r) = qr(columns, Householder::new(3), 1e-9, Strategy::Full);
+
+        let qtq = q.t().dot(&q);
+        for ((i, j), &v) in qtq.indexed_iter() {
+            let expect = if i == j { 1.0 } else { 0.0 };
+            assert!((v - expect).abs() < 1e-8);
+        }
+
+        let reconstructed = q.dot(&r);
+        for (x, y) in reconstructed.iter().zip(a.iter()) {
+            assert!((x - y).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn householder_qr_is_unitary_for_complex_input() {
+        let a = array![
+            [C::new(1.0, 1.0), C::new(2.0, -1.0), C::new(0.0, 3.0)],
+            [C::new(0.0, -2.0), C::new(1.0, 0.0), C::new(-1.0, 1.0)],
+            [C::new(3.0, 0.0), C::new(-2.0, 2.0), C::new(1.0, -1.0)],
+        ];
+
+        let columns = a.gencolumns().into_iter().map(|c| c.to_owned());
+        let (q, r) = qr(columns, Householder::new(3), 1e-9, Strategy::Full);
+
+        // conjugate-correct projection is what makes this come out unitary; with a
+        // naive (non-conjugated) inner product this would fail for complex input
+        let qhq = q.t().mapv(|x| x.conj()).dot(&q);
+        for ((i, j), v) in qhq.indexed_iter() {
+            let expect = if i == j { C::new(1.0, 0.0) } else { C::new(0.0, 0.0) };
+            assert!((v - expect).norm() < 1e-8);
+        }
+
+        let reconstructed = q.dot(&r);
+        for (x, y) in reconstructed.iter().zip(a.iter()) {
+            assert!((x - y).norm() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn householder_terminates_on_rank_deficient_input() {
+        // third column is the sum of the first two: linearly dependent
+        let v1 = array![1.0, 0.0, 0.0];
+        let v2 = array![0.0, 1.0, 0.0];
+        let v3 = &v1 + &v2;
+
+        let columns = vec![v1, v2, v3].into_iter();
+        let (q, r) = qr(columns, Householder::new(3), 1e-9, Strategy::Terminate);
+
+        // iteration must stop as soon as the dependent column is hit
+        assert_eq!(q.len_of(Axis(1)), 2);
+        assert_eq!(r.len_of(Axis(1)), 2);
+    }
+
+    #[test]
+    fn householder_skips_rank_deficient_input() {
+        // third column is dependent, fourth is independent of the first two
+        let v1 = array![1.0, 0.0, 0.0];
+        let v2 = array![0.0, 1.0, 0.0];
+        let v3 = &v1 + &v2;
+        let v4 = array![0.0, 0.0, 1.0];
+
+        let columns = vec![v1, v2, v3, v4].into_iter();
+        let (q, r) = qr(columns, Householder::new(3), 1e-9, Strategy::Skip);
+
+        // the dependent column is skipped, but the independent one after it is kept
+        assert_eq!(q.len_of(Axis(1)), 3);
+        assert_eq!(r.len_of(Axis(1)), 3);
+
+        let qtq = q.t().dot(&q);
+        for ((i, j), &v) in qtq.indexed_iter() {
+            let expect = if i == j { 1.0 } else { 0.0 };
+            assert!((v - expect).abs() < 1e-8);
+        }
+    }
+}
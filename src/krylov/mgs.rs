@@ -0,0 +1,148 @@
+//! Modified Gram-Schmidt online QR
+
+use super::*;
+
+/// Online QR decomposition using modified Gram-Schmidt
+pub struct MGS<A: Scalar> {
+    dim: usize,
+    q: Vec<Array1<A>>,
+}
+
+impl<A: Scalar + Lapack> MGS<A> {
+    pub fn new(dim: usize) -> Self {
+        MGS { dim, q: Vec::new() }
+    }
+}
+
+impl<A: Scalar + Lapack> Orthogonalizer for MGS<A> {
+    type Elem = A;
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn len(&self) -> usize {
+        self.q.len()
+    }
+
+    fn orthogonalize<S>(&self, a: &mut ArrayBase<S, Ix1>) -> Array1<A>
+    where
+        S: DataMut<Elem = A>,
+    {
+        assert_eq!(a.len(), self.dim);
+
+        let mut coef = Array1::zeros(self.len() + 1);
+        for (i, q) in self.q.iter().enumerate() {
+            // <q_i, a> with q_i conjugated, so the projection is correct over Complex<_>
+            let c = q
+                .iter()
+                .zip(a.iter())
+                .fold(A::zero(), |acc, (&qi, &ai)| acc + qi.conj() * ai);
+            coef[i] = c;
+
+            for (ai, &qi) in a.iter_mut().zip(q.iter()) {
+                *ai -= c * qi;
+            }
+        }
+
+        let norm = a.iter().map(|x| x.square()).sum::<A::Real>().sqrt();
+        coef[self.len()] = A::from_real(norm);
+
+        coef
+    }
+
+    fn append<S>(
+        &mut self,
+        mut a: ArrayBase<S, Ix1>,
+        rtol: A::Real,
+    ) -> Result<Array1<A>, Array1<A>>
+    where
+        S: DataMut<Elem = A>,
+    {
+        assert_eq!(a.len(), self.dim);
+
+        let coef = self.orthogonalize(&mut a);
+        let norm = coef[coef.len() - 1].abs();
+
+        if norm < rtol {
+            Err(coef)
+        } else {
+            let q = a.mapv(|x| x / A::from_real(norm));
+            self.q.push(q.to_owned());
+            Ok(coef)
+        }
+    }
+
+    fn get_q(&self) -> Q<A> {
+        let mut q = Array2::zeros((self.dim, self.len()));
+        for (i, qi) in self.q.iter().enumerate() {
+            q.column_mut(i).assign(qi);
+        }
+
+        q
+    }
+}
+
+/// One-shot modified Gram-Schmidt QR decomposition over `iter`
+pub fn mgs<A, S>(
+    iter: impl Iterator<Item = ArrayBase<S, Ix1>>,
+    dim: usize,
+    rtol: A::Real,
+    strategy: Strategy,
+) -> (Q<A>, R<A>)
+where
+    A: Scalar + Lapack,
+    S: Data<Elem = A>,
+{
+    qr(iter, MGS::new(dim), rtol, strategy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64 as C;
+
+    #[test]
+    fn mgs_qr_is_unitary_for_real_input() {
+        let a = array![[1.0, 2.0, 0.0], [0.0, 1.0, -1.0], [3.0, -2.0, 1.0]];
+
+        let columns = a.gencolumns().into_iter().map(|c| c.to_owned());
+        let (q, r) = qr(columns, MGS::new(3), 1e-9, Strategy::Full);
+
+        let qtq = q.t().dot(&q);
+        for ((i, j), &v) in qtq.indexed_iter() {
+            let expect = if i == j { 1.0 } else { 0.0 };
+            assert!((v - expect).abs() < 1e-8);
+        }
+
+        let reconstructed = q.dot(&r);
+        for (x, y) in reconstructed.iter().zip(a.iter()) {
+            assert!((x - y).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn mgs_qr_is_unitary_for_complex_input() {
+        let a = array![
+            [C::new(1.0, 1.0), C::new(2.0, -1.0), C::new(0.0, 3.0)],
+            [C::new(0.0, -2.0), C::new(1.0, 0.0), C::new(-1.0, 1.0)],
+            [C::new(3.0, 0.0), C::new(-2.0, 2.0), C::new(1.0, -1.0)],
+        ];
+
+        let columns = a.gencolumns().into_iter().map(|c| c.to_owned());
+        let (q, r) = qr(columns, MGS::new(3), 1e-9, Strategy::Full);
+
+        // conjugate-correct projection is what makes this come out unitary; with a
+        // naive (non-conjugated) inner product this would fail for complex input
+        let qhq = q.t().mapv(|x| x.conj()).dot(&q);
+        for ((i, j), v) in qhq.indexed_iter() {
+            let expect = if i == j { C::new(1.0, 0.0) } else { C::new(0.0, 0.0) };
+            assert!((v - expect).norm() < 1e-8);
+        }
+
+        let reconstructed = q.dot(&r);
+        for (x, y) in reconstructed.iter().zip(a.iter()) {
+            assert!((x - y).norm() < 1e-8);
+        }
+    }
+}
@@ -3,8 +3,12 @@
 use crate::types::*;
 use ndarray::*;
 
+mod arnoldi;
+mod householder;
 mod mgs;
 
+pub use arnoldi::arnoldi;
+pub use householder::Householder;
 pub use mgs::{mgs, MGS};
 
 /// Q-matrix
@@ -42,6 +46,11 @@ pub trait Orthogonalizer {
 
     /// Orthogonalize given vector using current basis
     ///
+    /// The `i`-th returned coefficient is the projection `<q_i, a>` with `q_i`
+    /// conjugated, so that `a - sum_i coef[i] * q_i` is the residual; this
+    /// must hold for `Self::Elem = Complex<_>` just as for real scalars, or
+    /// the accumulated `Q` will not come out unitary.
+    ///
     /// Panic
     /// -------
     /// - if the size of the input array mismatches to the dimension
@@ -54,10 +63,14 @@ pub trait Orthogonalizer {
     ///
     /// Returns
     /// --------
-    /// Coefficients to the `i`-th Q-vector
+    /// Coefficients to the `i`-th Q-vector, conjugate-correct in the same
+    /// sense as [`Orthogonalizer::orthogonalize`]
     ///
     /// - The size of array must be `self.len() + 1`
-    /// - The last element is the residual norm of input vector
+    /// - The last element is the residual norm (`Self::Elem::Real`, i.e. `|.|`
+    ///   rather than a real-only absolute value) of input vector; when the
+    ///   vector is accepted, `Householder` returns instead the diagonal entry
+    ///   of its `R`, whose absolute value is that norm
     ///
     /// Panic
     /// -------
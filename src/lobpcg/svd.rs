@@ -0,0 +1,257 @@
+///! Implements truncated singular value decomposition
+///
+
+use ndarray::prelude::*;
+use ndarray_rand::rand_distr::Uniform;
+use ndarray_rand::RandomExt;
+use num_traits::{Float, NumCast};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use crate::{Scalar, Lapack};
+use super::lobpcg::{lobpcg, EigResult, Order};
+
+/// Result of a truncated SVD, holding the eigenvalues and eigenvectors of the
+/// smaller Gram operator from which the singular triplets are recovered
+pub struct TruncatedSvdResult<A> {
+    eigvals: Array1<A>,
+    eigvecs: Array2<A>,
+    problem: Array2<A>,
+    order: Order,
+    converged: bool,
+}
+
+impl<A: Float + Scalar + Lapack + PartialOrd + Default> TruncatedSvdResult<A> {
+    /// Whether the underlying LOBPCG run converged to the requested precision
+    ///
+    /// `values`/`values_vectors` still return their best estimate when this is
+    /// `false`, but callers that need to distinguish a converged result from a
+    /// stale one should check this first.
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+
+    /// Singular values in the requested order, dropping any component whose
+    /// value falls below `1e-10`
+    pub fn values(&self) -> Array1<A> {
+        self.singular_values_and_indices().0
+    }
+
+    /// Singular values together with the left- and right-singular vectors
+    ///
+    /// Returns `(sigma, u, vt)` such that `problem ≈ u * diag(sigma) * vt`
+    pub fn values_vectors(&self) -> (Array1<A>, Array2<A>, Array2<A>) {
+        let (sigma, indices) = self.singular_values_and_indices();
+
+        let m = self.problem.len_of(Axis(0));
+        let n = self.problem.len_of(Axis(1));
+
+        let mut u = Array2::zeros((m, indices.len()));
+        let mut vt = Array2::zeros((indices.len(), n));
+
+        for (col, &idx) in indices.iter().enumerate() {
+            let s = sigma[col];
+            let v = self.eigvecs.column(idx);
+
+            if n <= m {
+                // eigvecs are the right-singular vectors of M^T M
+                let u_col = self.problem.dot(&v).mapv(|x| x / s);
+                u.column_mut(col).assign(&u_col);
+                vt.row_mut(col).assign(&v);
+            } else {
+                // eigvecs are the left-singular vectors of M M^T
+                let vt_row = self.problem.t().dot(&v).mapv(|x| x / s);
+                u.column_mut(col).assign(&v);
+                vt.row_mut(col).assign(&vt_row);
+            }
+        }
+
+        (sigma, u, vt)
+    }
+
+    fn singular_values_and_indices(&self) -> (Array1<A>, Vec<usize>) {
+        let tolerance: A = NumCast::from(1e-10).unwrap();
+
+        let mut pairs = self
+            .eigvals
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &lambda)| {
+                let lambda = if lambda > A::zero() { lambda } else { A::zero() };
+                let sigma = lambda.sqrt();
+
+                if sigma > tolerance {
+                    Some((i, sigma))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        match self.order {
+            Order::Largest => pairs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap()),
+            Order::Smallest => pairs.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap()),
+        }
+
+        let indices = pairs.iter().map(|x| x.0).collect();
+        let values = Array1::from(pairs.into_iter().map(|x| x.1).collect::<Vec<_>>());
+
+        (values, indices)
+    }
+}
+
+/// Truncated singular value decomposition, computed with the LOBPCG solver
+pub struct TruncatedSvd<A: Scalar, R: Rng = SmallRng> {
+    order: Order,
+    problem: Array2<A>,
+    precision: A::Real,
+    maxiter: usize,
+    rng: R,
+}
+
+impl<A: Scalar + Lapack + PartialOrd + Default> TruncatedSvd<A, SmallRng> {
+    pub fn new(problem: Array2<A>, order: Order) -> TruncatedSvd<A, SmallRng> {
+        TruncatedSvd::new_with_rng(problem, order, SmallRng::from_entropy())
+    }
+}
+
+impl<A: Scalar + Lapack + PartialOrd + Default, R: Rng> TruncatedSvd<A, R> {
+    pub fn new_with_rng(problem: Array2<A>, order: Order, rng: R) -> TruncatedSvd<A, R> {
+        TruncatedSvd {
+            precision: NumCast::from(1e-5).unwrap(),
+            maxiter: problem.len_of(Axis(0)) * 2,
+            order,
+            problem,
+            rng,
+        }
+    }
+
+    pub fn precision(mut self, precision: A::Real) -> Self {
+        self.precision = precision;
+
+        self
+    }
+
+    pub fn maxiter(mut self, maxiter: usize) -> Self {
+        self.maxiter = maxiter;
+
+        self
+    }
+}
+
+impl<A: Float + Scalar + Lapack + PartialOrd + Default, R: Rng> TruncatedSvd<A, R> {
+    /// Compute the `num` singular triplets of `problem` selected by `self.order`
+    ///
+    /// `self.order` is forwarded to `lobpcg` as-is: it picks the largest or
+    /// smallest eigenpairs of the Gram operator directly, the same way
+    /// `TruncatedEig` already does for the standard eigenproblem.
+    pub fn decompose(&mut self, num: usize) -> TruncatedSvdResult<A> {
+        let m = self.problem.len_of(Axis(0));
+        let n = self.problem.len_of(Axis(1));
+
+        // project onto the smaller of the two Gram operators, M^T M or M M^T
+        let size = if n <= m { n } else { m };
+
+        let x = Array2::random_using((size, num), Uniform::new(0.0, 1.0), &mut self.rng)
+            .mapv(|x| NumCast::from(x).unwrap());
+
+        let problem = self.problem.clone();
+        let res = if n <= m {
+            lobpcg(
+                |y| problem.t().dot(&problem.dot(&y)),
+                x,
+                None,
+                None,
+                self.precision,
+                self.maxiter,
+                self.order.clone(),
+            )
+        } else {
+            lobpcg(
+                |y| problem.dot(&problem.t().dot(&y)),
+                x,
+                None,
+                None,
+                self.precision,
+                self.maxiter,
+                self.order.clone(),
+            )
+        };
+
+        let (eigvals, eigvecs, converged) = match res {
+            EigResult::Ok(vals, vecs, _) => (vals, vecs, true),
+            EigResult::Err(vals, vecs, _, _) => (vals, vecs, false),
+            EigResult::NoResult(_) => (Array1::zeros(0), Array2::zeros((size, 0)), false),
+        };
+
+        TruncatedSvdResult {
+            eigvals,
+            eigvecs,
+            problem: self.problem.clone(),
+            order: self.order.clone(),
+            converged,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TruncatedSvd;
+    use super::Order;
+    use ndarray::Array2;
+    use ndarray_rand::rand_distr::Uniform;
+    use ndarray_rand::RandomExt;
+
+    fn reconstruction_error(a: &Array2<f64>, s: &ndarray::Array1<f64>, u: &Array2<f64>, vt: &Array2<f64>) -> f64 {
+        let reconstructed = u.dot(&Array2::from_diag(s)).dot(vt);
+        (a - &reconstructed).iter().map(|x| x * x).sum::<f64>().sqrt()
+    }
+
+    #[test]
+    fn test_truncated_svd_largest() {
+        let a = Array2::random((10, 5), Uniform::new(0., 1.0));
+
+        let mut tsvd = TruncatedSvd::new(a.clone(), Order::Largest)
+            .precision(1e-5)
+            .maxiter(500);
+
+        let res = tsvd.decompose(5);
+        assert!(res.converged());
+
+        let (s, u, vt) = res.values_vectors();
+        assert_eq!(u.len_of(ndarray::Axis(0)), 10);
+        assert_eq!(vt.len_of(ndarray::Axis(1)), 5);
+
+        // with all 5 singular triplets of a 10x5 matrix, the reconstruction is exact
+        assert!(reconstruction_error(&a, &s, &u, &vt) < 1e-3);
+    }
+
+    #[test]
+    fn test_truncated_svd_smallest() {
+        // full rank is 10, so requesting 2 of 10 leaves a wide gap between the
+        // largest-2 and smallest-2 singular values: a regression to a hard-coded
+        // `Order::Largest` would make this fail instead of trivially pass
+        let a = Array2::random((10, 10), Uniform::new(0., 1.0));
+
+        let mut tsvd_largest = TruncatedSvd::new(a.clone(), Order::Largest)
+            .precision(1e-5)
+            .maxiter(500);
+        let mut tsvd_smallest = TruncatedSvd::new(a.clone(), Order::Smallest)
+            .precision(1e-5)
+            .maxiter(500);
+
+        let largest = tsvd_largest.decompose(2).values();
+        let smallest = tsvd_smallest.decompose(2).values();
+
+        // the two requested subsets are genuinely different, not the same values re-sorted
+        for l in largest.iter() {
+            for s in smallest.iter() {
+                assert!((l - s).abs() > 1e-2);
+            }
+        }
+
+        // largest-2 must dominate smallest-2
+        let min_largest = largest.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max_smallest = smallest.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        assert!(min_largest > max_smallest);
+    }
+}